@@ -1,3 +1,7 @@
+mod queue_stack;
+
+pub use queue_stack::QueueStack;
+
 /// A generic `Stack` implementation using a `Vec` as the underlying storage.
 ///
 /// The `Stack` struct provides standard stack operations:
@@ -6,50 +10,276 @@
 /// - Peek: View the element at the top of the stack without removing it.
 /// - Check if the stack is empty.
 /// - Get the number of elements in the stack.
+///
+/// `Stack` also supports checkpointing via [`Stack::snapshot`], [`Stack::restore`] and
+/// [`Stack::clear_snapshot`], in the style of pest's rewindable stack: while at least one
+/// snapshot is live, every mutation is recorded in an internal op log, and a snapshot
+/// remembers how far back that log needs to be replayed to undo everything since it was
+/// taken. Without an active snapshot the log is empty and mutations skip it entirely, so
+/// plain push/pop usage (e.g. as a VM operand stack) pays no logging or cloning cost.
 pub struct Stack<T> {
-    // stack items are private by default
-    items: Vec<T>,
+    // the live contents of the stack; private by default
+    cache: Vec<T>,
+    // a log of every push/pop/swap performed while at least one snapshot is live
+    ops: Vec<StackOp<T>>,
+    // op-log lengths recorded at each `snapshot()` call, forming their own stack
+    snapshots: Vec<usize>,
+    // upper bound on the number of items, if the stack was created via `with_max_size`
+    max_size: Option<usize>,
+}
+
+/// A single recorded mutation of a `Stack`, used to rewind it via [`Stack::restore`].
+enum StackOp<T> {
+    /// An item was pushed; undone by popping it back off.
+    Push,
+    /// An item was popped; undone by pushing the saved item back on.
+    Pop(T),
+    /// Two indices were swapped; undone by swapping them back (a swap is its own inverse).
+    Swap(usize, usize),
 }
 
 impl<T> Stack<T> {
-    /// Creates a new, empty `Stack`.
+    /// Creates a new, empty `Stack` with no upper bound on its size.
     pub fn new() -> Self {
-        Stack { items: Vec::new() }
+        Stack {
+            cache: Vec::new(),
+            ops: Vec::new(),
+            snapshots: Vec::new(),
+            max_size: None,
+        }
+    }
+
+    /// Creates a new, empty `Stack` that rejects pushes past `maxsize` elements via
+    /// [`Stack::try_push`].
+    pub fn with_max_size(maxsize: usize) -> Self {
+        Stack {
+            cache: Vec::new(),
+            ops: Vec::new(),
+            snapshots: Vec::new(),
+            max_size: Some(maxsize),
+        }
     }
 
     /// Pushes an item onto the top of the stack.
     ///
+    /// For a bounded stack created via [`Stack::with_max_size`], this does not enforce
+    /// the bound; use [`Stack::try_push`] when overflow must be rejected rather than
+    /// grown past.
+    ///
     /// # Arguments
     ///
     /// * `item` - The item to be pushed onto the stack.
     pub fn push(&mut self, item: T) {
-        self.items.push(item);
+        self.cache.push(item);
+        if !self.snapshots.is_empty() {
+            self.ops.push(StackOp::Push);
+        }
+    }
+
+    /// Pushes an item onto the stack unless it is already at its configured maximum
+    /// size, in which case the item is handed back to the caller.
+    ///
+    /// A stack created via [`Stack::new`] has no maximum size and never rejects a push.
+    pub fn try_push(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(item);
+        }
+        self.push(item);
+        Ok(())
+    }
+
+    /// Returns `true` if the stack was created with [`Stack::with_max_size`] and has
+    /// reached that limit.
+    pub fn is_full(&self) -> bool {
+        self.max_size.is_some_and(|max| self.cache.len() >= max)
+    }
+
+    /// Returns the configured maximum size, or `None` if the stack is unbounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.max_size
     }
 
     /// Removes and returns the item at the top of the stack.
     ///
     /// Returns `None` if the stack is empty.
-    pub fn pop(&mut self) -> Option<T> {
-        self.items.pop()
+    pub fn pop(&mut self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let item = self.cache.pop()?;
+        if !self.snapshots.is_empty() {
+            self.ops.push(StackOp::Pop(item.clone()));
+        }
+        Some(item)
     }
 
     /// Returns a reference to the item at the top of the stack without removing it.
     ///
     /// Returns `None` if the stack is empty.
     pub fn peek(&self) -> Option<&T> {
-        self.items.last()
+        self.cache.last()
     }
 
     /// Checks if the stack is empty.
     ///
     /// Returns `true` if the stack is empty, otherwise `false`.
     pub fn is_empty(&self) -> bool {
-        self.items.is_empty()
+        self.cache.is_empty()
     }
 
     /// Returns the number of items in the stack.
     pub fn len(&self) -> usize {
-        self.items.len()
+        self.cache.len()
+    }
+
+    /// Checks whether at least `n` elements are present on the stack.
+    pub fn has(&self, n: usize) -> bool {
+        self.cache.len() >= n
+    }
+
+    /// Returns a reference to the element `from_top` positions below the top, without
+    /// popping anything. `from_top == 0` is the current top, mirroring [`Stack::peek`].
+    ///
+    /// Returns `None` if the stack has fewer than `from_top + 1` elements.
+    pub fn peek_at(&self, from_top: usize) -> Option<&T> {
+        let len = self.cache.len();
+        let index = from_top.checked_add(1).and_then(|n| len.checked_sub(n))?;
+        self.cache.get(index)
+    }
+
+    /// Swaps the top element with the one `from_top` positions below it, useful for
+    /// implementing operand-stack opcodes like EVM-style `SWAPn`.
+    ///
+    /// Returns `false` without modifying the stack if `from_top` is out of range.
+    pub fn swap_with_top(&mut self, from_top: usize) -> bool {
+        let len = self.cache.len();
+        let Some(index) = from_top.checked_add(1).and_then(|n| len.checked_sub(n)) else {
+            return false;
+        };
+        let top = len - 1;
+        self.cache.swap(index, top);
+        if !self.snapshots.is_empty() {
+            self.ops.push(StackOp::Swap(index, top));
+        }
+        true
+    }
+
+    /// Exposes the underlying contents read-only, bottom first and top last.
+    pub fn as_slice(&self) -> &[T] {
+        &self.cache
+    }
+
+    /// Removes and returns the top `n` elements in pop order (former top first).
+    ///
+    /// Returns `None`, leaving the stack untouched, if fewer than `n` elements are present.
+    pub fn pop_n(&mut self, n: usize) -> Option<Vec<T>>
+    where
+        T: Clone,
+    {
+        if !self.has(n) {
+            return None;
+        }
+        Some((0..n).map(|_| self.pop().unwrap()).collect())
+    }
+
+    /// Pushes many items onto the stack at once, in iteration order.
+    pub fn push_n(&mut self, items: impl IntoIterator<Item = T>) {
+        for item in items {
+            self.push(item);
+        }
+    }
+
+    /// Records a checkpoint of the current stack state.
+    ///
+    /// A later call to [`Stack::restore`] rolls the stack back to exactly this point.
+    /// Snapshots nest: taking a snapshot while another is still live pushes onto the
+    /// same snapshot stack, and each `restore`/`clear_snapshot` pops the most recent one.
+    pub fn snapshot(&mut self) {
+        self.snapshots.push(self.ops.len());
+    }
+
+    /// Rolls the stack back to the most recent [`Stack::snapshot`].
+    ///
+    /// Does nothing if there is no snapshot to restore to. Every push recorded since the
+    /// snapshot is undone by popping it back off; every pop is undone by pushing the saved
+    /// item back on; every swap (from [`Stack::swap_with_top`]) is undone by swapping the
+    /// same two indices again. All replayed in reverse order.
+    pub fn restore(&mut self) {
+        let Some(mark) = self.snapshots.pop() else {
+            return;
+        };
+        for op in self.ops.drain(mark..).rev() {
+            match op {
+                StackOp::Push => {
+                    self.cache.pop();
+                }
+                StackOp::Pop(item) => {
+                    self.cache.push(item);
+                }
+                StackOp::Swap(a, b) => {
+                    self.cache.swap(a, b);
+                }
+            }
+        }
+        if self.snapshots.is_empty() {
+            self.ops.clear();
+        }
+    }
+
+    /// Discards the most recent snapshot without rolling back, committing any changes
+    /// made since it was taken.
+    ///
+    /// Does nothing if there is no snapshot recorded.
+    pub fn clear_snapshot(&mut self) {
+        self.snapshots.pop();
+        if self.snapshots.is_empty() {
+            self.ops.clear();
+        }
+    }
+
+    /// Returns an iterator over references to the items, in pop order (top first).
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cache.iter().rev()
+    }
+
+    /// Removes and returns every item, in pop order (top first), leaving the stack
+    /// empty and discarding any recorded snapshots.
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.ops.clear();
+        self.snapshots.clear();
+        self.cache.drain(..).rev()
+    }
+}
+
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Stack::new()
+    }
+}
+
+impl<T> FromIterator<T> for Stack<T> {
+    /// Builds a stack from an iterator, pushing items in iteration order so the last
+    /// item produced ends up on top.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = Stack::new();
+        stack.push_n(iter);
+        stack
+    }
+}
+
+impl<T> Extend<T> for Stack<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.push_n(iter);
+    }
+}
+
+impl<T> IntoIterator for Stack<T> {
+    type Item = T;
+    type IntoIter = std::iter::Rev<std::vec::IntoIter<T>>;
+
+    /// Consumes the stack, yielding items in pop order (top first).
+    fn into_iter(self) -> Self::IntoIter {
+        self.cache.into_iter().rev()
     }
 }
 
@@ -97,4 +327,187 @@ mod tests {
         // Should get a compile error if next line uncommented
         // let _stack3: Stack<i32> = stack1; // Error: `stack1` has been moved
     }
+
+    #[test]
+    fn test_snapshot_restore_discards_pushes() {
+        let mut stack: Stack<i32> = Stack::new();
+        stack.push(1);
+        stack.snapshot();
+        stack.push(2);
+        stack.push(3);
+        stack.restore();
+        assert_eq!(stack.len(), 1);
+        assert_eq!(stack.pop(), Some(1));
+    }
+
+    #[test]
+    fn test_snapshot_restore_undoes_pops() {
+        let mut stack: Stack<i32> = Stack::new();
+        stack.push(1);
+        stack.push(2);
+        stack.snapshot();
+        assert_eq!(stack.pop(), Some(2));
+        assert_eq!(stack.pop(), Some(1));
+        stack.restore();
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.pop(), Some(2));
+        assert_eq!(stack.pop(), Some(1));
+    }
+
+    #[test]
+    fn test_clear_snapshot_commits_changes() {
+        let mut stack: Stack<i32> = Stack::new();
+        stack.push(1);
+        stack.snapshot();
+        stack.push(2);
+        stack.clear_snapshot();
+        stack.restore(); // no snapshot left, should be a no-op
+        assert_eq!(stack.len(), 2);
+    }
+
+    #[test]
+    fn test_ops_log_is_empty_without_a_snapshot() {
+        let mut stack: Stack<i32> = Stack::new();
+        for i in 0..1000 {
+            stack.push(i);
+        }
+        for _ in 0..1000 {
+            stack.pop();
+        }
+        assert_eq!(stack.ops.len(), 0);
+    }
+
+    #[test]
+    fn test_has_and_as_slice() {
+        let mut stack: Stack<i32> = Stack::new();
+        assert!(stack.has(0));
+        assert!(!stack.has(1));
+        stack.push(1);
+        stack.push(2);
+        assert!(stack.has(2));
+        assert!(!stack.has(3));
+        assert_eq!(stack.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_unbounded_stack_has_no_capacity() {
+        let stack: Stack<i32> = Stack::new();
+        assert_eq!(stack.capacity(), None);
+        assert!(!stack.is_full());
+    }
+
+    #[test]
+    fn test_try_push_rejects_past_max_size() {
+        let mut stack: Stack<i32> = Stack::with_max_size(2);
+        assert_eq!(stack.capacity(), Some(2));
+        assert_eq!(stack.try_push(1), Ok(()));
+        assert_eq!(stack.try_push(2), Ok(()));
+        assert!(stack.is_full());
+        assert_eq!(stack.try_push(3), Err(3));
+        assert_eq!(stack.len(), 2);
+    }
+
+    #[test]
+    fn test_peek_at() {
+        let mut stack: Stack<i32> = Stack::new();
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert_eq!(stack.peek_at(0), Some(&3));
+        assert_eq!(stack.peek_at(1), Some(&2));
+        assert_eq!(stack.peek_at(2), Some(&1));
+        assert_eq!(stack.peek_at(3), None);
+        assert_eq!(stack.peek_at(usize::MAX), None);
+    }
+
+    #[test]
+    fn test_swap_with_top() {
+        let mut stack: Stack<i32> = Stack::new();
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert!(stack.swap_with_top(2));
+        assert_eq!(stack.as_slice(), &[3, 2, 1]);
+        assert!(!stack.swap_with_top(3));
+        assert_eq!(stack.as_slice(), &[3, 2, 1]);
+        assert!(!stack.swap_with_top(usize::MAX));
+        assert_eq!(stack.as_slice(), &[3, 2, 1]);
+    }
+
+    #[test]
+    fn test_restore_undoes_swap_with_top() {
+        let mut stack: Stack<i32> = Stack::new();
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        stack.snapshot();
+        assert!(stack.swap_with_top(2));
+        assert_eq!(stack.as_slice(), &[3, 2, 1]);
+        stack.restore();
+        assert_eq!(stack.as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_pop_n() {
+        let mut stack: Stack<i32> = Stack::new();
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert_eq!(stack.pop_n(2), Some(vec![3, 2]));
+        assert_eq!(stack.len(), 1);
+        assert_eq!(stack.pop_n(2), None);
+        assert_eq!(stack.len(), 1);
+    }
+
+    #[test]
+    fn test_push_n() {
+        let mut stack: Stack<i32> = Stack::new();
+        stack.push_n(vec![1, 2, 3]);
+        assert_eq!(stack.as_slice(), &[1, 2, 3]);
+        assert_eq!(stack.pop(), Some(3));
+    }
+
+    #[test]
+    fn test_nested_snapshots() {
+        let mut stack: Stack<i32> = Stack::new();
+        stack.push(1);
+        stack.snapshot();
+        stack.push(2);
+        stack.snapshot();
+        stack.push(3);
+        stack.restore();
+        assert_eq!(stack.len(), 2);
+        stack.restore();
+        assert_eq!(stack.len(), 1);
+    }
+
+    #[test]
+    fn test_from_iterator_and_extend() {
+        let mut stack: Stack<i32> = (1..=3).collect();
+        assert_eq!(stack.as_slice(), &[1, 2, 3]);
+        stack.extend(vec![4, 5]);
+        assert_eq!(stack.as_slice(), &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_iter_is_top_first() {
+        let stack: Stack<i32> = vec![1, 2, 3].into_iter().collect();
+        let collected: Vec<&i32> = stack.iter().collect();
+        assert_eq!(collected, vec![&3, &2, &1]);
+    }
+
+    #[test]
+    fn test_into_iterator_is_pop_order() {
+        let stack: Stack<i32> = vec![1, 2, 3].into_iter().collect();
+        let collected: Vec<i32> = stack.into_iter().collect();
+        assert_eq!(collected, vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_drain_empties_top_to_bottom() {
+        let mut stack: Stack<i32> = vec![1, 2, 3].into_iter().collect();
+        let drained: Vec<i32> = stack.drain().collect();
+        assert_eq!(drained, vec![3, 2, 1]);
+        assert!(stack.is_empty());
+    }
 }
@@ -0,0 +1,166 @@
+use std::collections::VecDeque;
+
+/// A combined stack and queue for LR-style parsing with lookahead: shifted tokens
+/// accumulate on the stack side while pending lookahead lives on the queue side.
+///
+/// Internally this pairs a `Vec` (the stack side) with a `VecDeque` (the queue side) so
+/// that `push`/`pop`/`enqueue` are all O(1) amortized, and moving a token between the
+/// front of the queue and the top of the stack (`shift`/`unshift`) is a single O(1) move
+/// of that one element — it is never cloned, and the rest of either side is untouched.
+pub struct QueueStack<T> {
+    stack: Vec<T>,
+    queue: VecDeque<T>,
+}
+
+impl<T> QueueStack<T> {
+    /// Creates a new, empty `QueueStack`.
+    pub fn new() -> Self {
+        QueueStack {
+            stack: Vec::new(),
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Pushes an item onto the top of the stack side.
+    pub fn push(&mut self, item: T) {
+        self.stack.push(item);
+    }
+
+    /// Removes and returns the item at the top of the stack side.
+    ///
+    /// Returns `None` if the stack side is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.stack.pop()
+    }
+
+    /// Appends an item to the tail of the queue side.
+    pub fn enqueue(&mut self, item: T) {
+        self.queue.push_back(item);
+    }
+
+    /// Moves the front element of the queue side onto the top of the stack side,
+    /// returning a reference to it in its new position.
+    ///
+    /// This is a single O(1) move of that one element; it is not cloned. Returns `None`
+    /// if the queue side is empty.
+    pub fn shift(&mut self) -> Option<&T> {
+        let item = self.queue.pop_front()?;
+        self.stack.push(item);
+        self.stack.last()
+    }
+
+    /// Moves the top element of the stack side back to the front of the queue side,
+    /// returning a reference to it in its new position.
+    ///
+    /// Like [`QueueStack::shift`], this moves that one element without cloning it.
+    /// Returns `None` if the stack side is empty.
+    pub fn unshift(&mut self) -> Option<&T> {
+        let item = self.stack.pop()?;
+        self.queue.push_front(item);
+        self.queue.front()
+    }
+
+    /// Returns the number of items on the stack side.
+    pub fn stack_len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns the number of items on the queue side.
+    pub fn queue_len(&self) -> usize {
+        self.queue.len()
+    }
+}
+
+impl<T> Default for QueueStack<T> {
+    fn default() -> Self {
+        QueueStack::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut qs: QueueStack<i32> = QueueStack::new();
+        qs.push(1);
+        qs.push(2);
+        assert_eq!(qs.stack_len(), 2);
+        assert_eq!(qs.pop(), Some(2));
+        assert_eq!(qs.pop(), Some(1));
+        assert_eq!(qs.pop(), None);
+    }
+
+    #[test]
+    fn test_enqueue_and_shift() {
+        let mut qs: QueueStack<i32> = QueueStack::new();
+        qs.enqueue(1);
+        qs.enqueue(2);
+        qs.enqueue(3);
+        assert_eq!(qs.queue_len(), 3);
+        assert_eq!(qs.shift(), Some(&1));
+        assert_eq!(qs.stack_len(), 1);
+        assert_eq!(qs.queue_len(), 2);
+        assert_eq!(qs.pop(), Some(1));
+    }
+
+    #[test]
+    fn test_unshift_reverses_shift() {
+        let mut qs: QueueStack<i32> = QueueStack::new();
+        qs.enqueue(1);
+        qs.enqueue(2);
+        qs.shift();
+        qs.shift();
+        assert_eq!(qs.stack_len(), 2);
+        assert_eq!(qs.queue_len(), 0);
+        assert_eq!(qs.unshift(), Some(&2));
+        assert_eq!(qs.stack_len(), 1);
+        assert_eq!(qs.queue_len(), 1);
+    }
+
+    #[test]
+    fn test_shift_and_unshift_on_empty_side() {
+        let mut qs: QueueStack<i32> = QueueStack::new();
+        assert_eq!(qs.shift(), None);
+        assert_eq!(qs.unshift(), None);
+    }
+
+    #[test]
+    fn test_mixed_push_enqueue_shift() {
+        let mut qs: QueueStack<i32> = QueueStack::new();
+        qs.push(1);
+        qs.enqueue(2);
+        qs.enqueue(3);
+        assert_eq!(qs.shift(), Some(&2));
+        assert_eq!(qs.pop(), Some(2));
+        assert_eq!(qs.pop(), Some(1));
+        assert_eq!(qs.shift(), Some(&3));
+        assert_eq!(qs.pop(), Some(3));
+    }
+
+    #[test]
+    fn test_push_and_pop_are_not_affected_by_queue_length() {
+        // Regression test: push/pop must not shift the queue side around, so they stay
+        // cheap no matter how much lookahead is buffered (the LR shift/reduce case).
+        let mut qs: QueueStack<i32> = QueueStack::new();
+        for i in 0..1000 {
+            qs.enqueue(i);
+        }
+        qs.push(-1);
+        assert_eq!(qs.pop(), Some(-1));
+        assert_eq!(qs.queue_len(), 1000);
+    }
+
+    #[test]
+    fn test_shift_and_unshift_do_not_require_clone() {
+        // `NotClone` deliberately does not implement `Clone`; this only compiles if
+        // `shift`/`unshift` avoid requiring it.
+        struct NotClone(i32);
+
+        let mut qs: QueueStack<NotClone> = QueueStack::new();
+        qs.enqueue(NotClone(1));
+        assert_eq!(qs.shift().map(|item| item.0), Some(1));
+        assert_eq!(qs.unshift().map(|item| item.0), Some(1));
+    }
+}